@@ -1,27 +1,90 @@
 //! Safe-ish interface for reading and writing specific types to the WASM runtime's memory
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 use num_traits::ToPrimitive;
 use wasmer::{Memory, MemoryView};
 
-// TODO: Decide whether we want Ark here or if it should use a generic BigInt package
-use ark_bn254::FrParameters;
 use ark_ff::{BigInteger, BigInteger256, FpParameters, FromBytes, Zero};
+use ethers::types::{Address, U256};
 
-use num_bigint::{BigInt, BigUint, Sign};
+use num_bigint::{BigInt, BigUint};
 
 use color_eyre::Result;
-use std::{convert::TryFrom, ops::Deref};
+use std::{
+    convert::{TryFrom, TryInto},
+    io::{Cursor, Read, Write},
+    marker::PhantomData,
+    ops::Deref,
+};
+
+/// Byte order of the limbs `SafeMem` reads and writes. WASM linear memory is
+/// little-endian, which is what every known witness calculator targets, but the knob is
+/// kept explicit rather than assumed so a big-endian-compiled target isn't silently
+/// corrupted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Describes the prime field a WASM witness calculator was compiled for, so that
+/// [`SafeMem`] isn't welded to a single curve's `Fr`.
+///
+/// Mirrors snarkvm's `BigInteger` trait: an implementor names its canonical
+/// big-integer representation, how many 64-bit limbs that representation takes, and
+/// the field modulus. [`SafeMem`] uses the representation and limb count to marshal
+/// values in and out of WASM memory; it reduces values against the `prime` passed to
+/// [`SafeMem::new`], not against `MODULUS`.
+pub trait FieldParams {
+    /// Canonical big-integer representation of an element of this field. Constrained to
+    /// `Error = String` because that's what every `ark-ff` `BigInteger` impl uses, which
+    /// lets callers `.unwrap()` a failed conversion without each one separately proving
+    /// the error type is `Debug`.
+    type BigInt: BigInteger + TryFrom<BigUint, Error = String>;
+
+    /// Number of 64-bit limbs in `BigInt` (e.g. 4 for both BN254 and BLS12-381's scalar
+    /// field -- BLS12-381's ~381-bit base field `Fq` would need 6, but witness
+    /// calculators only ever operate over the scalar field `Fr`).
+    const NUM_LIMBS: usize;
+
+    /// The field modulus, as a `BigInt`.
+    const MODULUS: Self::BigInt;
+}
 
+/// [`FieldParams`] for circuits compiled against BN254's scalar field.
 #[derive(Clone, Debug)]
-pub struct SafeMem {
+pub struct Bn254Params;
+
+impl FieldParams for Bn254Params {
+    type BigInt = BigInteger256;
+    const NUM_LIMBS: usize = 4;
+    const MODULUS: Self::BigInt = ark_bn254::FrParameters::MODULUS;
+}
+
+/// [`FieldParams`] for circuits compiled against BLS12-381's scalar field.
+#[derive(Clone, Debug)]
+pub struct Bls12_381Params;
+
+impl FieldParams for Bls12_381Params {
+    // BLS12-381's scalar field Fr is ~255 bits, the same limb class as BN254 --
+    // `BigInteger384` is the 381-bit *base* field Fq, which witness calculators never
+    // touch.
+    type BigInt = BigInteger256;
+    const NUM_LIMBS: usize = 4;
+    const MODULUS: Self::BigInt = ark_bls12_381::FrParameters::MODULUS;
+}
+
+#[derive(Clone, Debug)]
+pub struct SafeMem<P: FieldParams = Bn254Params> {
     pub memory: Memory,
 
     short_max: BigInt,
     short_min: BigInt,
     pub prime: BigInt,
-    n32: usize,
+    endianness: Endianness,
+    _params: PhantomData<P>,
 }
 
-impl Deref for SafeMem {
+impl<P: FieldParams> Deref for SafeMem<P> {
     type Target = Memory;
 
     fn deref(&self) -> &Self::Target {
@@ -29,20 +92,27 @@ impl Deref for SafeMem {
     }
 }
 
-impl SafeMem {
-    pub fn new(memory: Memory, n32: usize, prime: BigInt) -> Self {
+impl<P: FieldParams> SafeMem<P> {
+    pub fn new(memory: Memory, prime: BigInt) -> Self {
+        Self::with_endianness(memory, prime, Endianness::Little)
+    }
+
+    pub fn with_endianness(memory: Memory, prime: BigInt, endianness: Endianness) -> Self {
+        // The short/long threshold is `±2^31` regardless of field -- it's just the range
+        // a value needs to fit in to be written as a plain (signed) i32 instead of a full
+        // field element, so it doesn't actually depend on the modulus. `write_short_negative`
+        // below relies on `short_min` being this small negative number, not a modulus-sized
+        // one, to recover the original value from its field encoding.
         let short_max = BigInt::from(0x8000_0000u64);
-        let short_min = BigInt::from_biguint(
-            num_bigint::Sign::NoSign,
-            BigUint::try_from(FrParameters::MODULUS).unwrap(),
-        ) - &short_max;
+        let short_min = -&short_max;
 
         Self {
             memory,
             short_max,
             short_min,
             prime,
-            n32,
+            endianness,
+            _params: PhantomData,
         }
     }
 
@@ -69,21 +139,29 @@ impl SafeMem {
     /// This is marked as `&mut self` for safety
     pub fn write_u32(&mut self, ptr: usize, num: u32) {
         let buf = unsafe { self.memory.data_unchecked_mut() };
-        buf[ptr..ptr + std::mem::size_of::<u32>()].copy_from_slice(&num.to_le_bytes());
+        let mut cursor = Cursor::new(&mut buf[ptr..ptr + std::mem::size_of::<u32>()]);
+        match self.endianness {
+            Endianness::Little => cursor.write_u32::<LittleEndian>(num),
+            Endianness::Big => cursor.write_u32::<BigEndian>(num),
+        }
+        .expect("writing a u32 into WASM memory cannot fail");
     }
 
     /// Reads a u32 from the specific slice
     pub fn read_u32(&self, ptr: usize) -> u32 {
         let buf = unsafe { self.memory.data_unchecked() };
-
-        let mut bytes = [0; 4];
-        bytes.copy_from_slice(&buf[ptr..ptr + std::mem::size_of::<u32>()]);
-
-        u32::from_le_bytes(bytes)
+        let mut cursor = Cursor::new(&buf[ptr..ptr + std::mem::size_of::<u32>()]);
+        match self.endianness {
+            Endianness::Little => cursor.read_u32::<LittleEndian>(),
+            Endianness::Big => cursor.read_u32::<BigEndian>(),
+        }
+        .expect("reading a u32 from WASM memory cannot fail")
     }
 
     pub fn alloc_fr(&mut self) -> u32 {
-        let n32 = 8;
+        // `NUM_LIMBS` 64-bit limbs, expressed in 32-bit words; the two header words are
+        // the extra 8 bytes below.
+        let n32 = P::NUM_LIMBS as u32 * 2;
         let p = self.free_pos();
         self.set_free_pos(p + n32 * 4 + 8);
         p
@@ -106,17 +184,17 @@ impl SafeMem {
     // https://github.com/iden3/go-circom-witnesscalc/blob/25592ab9b33bf8d6b99c133783bd208bee7a935c/witnesscalc.go#L410-L430
     // TODO: Figure out WTF all this parsing is for
     pub fn read_fr(&self, ptr: usize) -> Result<BigInt> {
-        let view = self.memory.view::<u32>();
-
-        let res = if view[ptr + 1].get() & 0x80000000 != 0 {
-            let num = self.read_big(ptr + 8, self.n32)?;
+        // Read the type/sign tag words through `read_u32` (not the raw memory view)
+        // so they honor `self.endianness` the same way `write_long_normal` wrote them.
+        let res = if self.read_u32(ptr + 4) & 0x80000000 != 0 {
+            let num = self.read_big(ptr + 8)?;
             num
         } else {
             // read the number
-            let mut res = self.read_big(ptr, 4).unwrap();
+            let mut res = BigInt::from(self.read_u32(ptr));
 
             // adjust the sign if negative
-            if view[ptr].get() & 0x80000000 != 0 {
+            if self.read_u32(ptr) & 0x80000000 != 0 {
                 res -= BigInt::from(0x100000000i64)
             }
             res
@@ -156,30 +234,353 @@ impl SafeMem {
     fn write_big(&self, ptr: usize, num: &BigInt) -> Result<()> {
         let buf = unsafe { self.memory.data_unchecked_mut() };
 
-        // always positive?
-        let (_, num) = num.clone().into_parts();
-        let num = BigInteger256::try_from(num).unwrap();
+        // Reduce to the canonical representative mod the field's prime, so a negative
+        // `num` is written as `p - |num|` rather than its bare magnitude.
+        let num = ((num % &self.prime) + &self.prime) % &self.prime;
+        let (_, num) = num.into_parts();
+        let num = P::BigInt::try_from(num).unwrap();
 
-        let bytes = num.to_bytes_le();
-        let len = bytes.len();
-        buf[ptr..ptr + len].copy_from_slice(&bytes);
+        let bytes = match self.endianness {
+            Endianness::Little => num.to_bytes_le(),
+            Endianness::Big => num.to_bytes_be(),
+        };
+        let mut cursor = Cursor::new(&mut buf[ptr..ptr + bytes.len()]);
+        cursor
+            .write_all(&bytes)
+            .expect("writing a big integer into WASM memory cannot fail");
 
         Ok(())
     }
 
-    pub fn read_big(&self, ptr: usize, num_bytes: usize) -> Result<BigInt> {
+    /// Reads a single field element's worth of limbs (`P::NUM_LIMBS * 8` bytes) starting
+    /// at `ptr`.
+    pub fn read_big(&self, ptr: usize) -> Result<BigInt> {
         let buf = unsafe { self.memory.data_unchecked() };
-        let buf = &buf[ptr..ptr + num_bytes * 32];
+        let mut cursor = Cursor::new(&buf[ptr..ptr + P::NUM_LIMBS * 8]);
+        let mut bytes = vec![0u8; P::NUM_LIMBS * 8];
+        cursor
+            .read_exact(&mut bytes)
+            .expect("reading a big integer from WASM memory cannot fail");
+        if self.endianness == Endianness::Big {
+            bytes.reverse();
+        }
 
-        // TODO: Is there a better way to read big integers?
-        let big = BigInteger256::read(buf).unwrap();
-        dbg!(&big);
-        let big = BigUint::try_from(big).unwrap();
+        let big = P::BigInt::read(&bytes[..]).unwrap();
+        // `BigInteger: Into<BigUint>` is infallible, unlike the `BigUint -> BigInteger`
+        // direction above in `write_big`.
+        let big: BigUint = big.into();
         Ok(big.into())
     }
+
+    /// Writes a raw byte slice to the provided position of the buffer
+    pub fn write_bytes(&mut self, ptr: usize, bytes: &[u8]) {
+        let buf = unsafe { self.memory.data_unchecked_mut() };
+        buf[ptr..ptr + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Reads `len` raw bytes starting at the provided position of the buffer
+    pub fn read_bytes(&self, ptr: usize, len: usize) -> Vec<u8> {
+        let buf = unsafe { self.memory.data_unchecked() };
+        buf[ptr..ptr + len].to_vec()
+    }
+
+    /// Reads each of `witness_ptrs` as an `Fr` and streams them out as an iden3 `.wtns`
+    /// file: the `wtns` magic, a version, a two-entry section table, a field-size header
+    /// (`n8` limb bytes followed by the prime itself), the witness count, and finally
+    /// every witness value as `n8` little-endian bytes.
+    ///
+    /// This is the same container `snarkjs`/arkworks-based provers expect, so a witness
+    /// computed in WASM memory can be handed to them without a bespoke re-encoding step.
+    ///
+    /// Reduces against `self.prime`, the same prime `write_fr`/`write_big` used when the
+    /// witness was written -- there's no separate prime to pass in, so the dump can't
+    /// silently diverge from what's actually in memory.
+    pub fn dump_witness<W: Write>(&self, witness_ptrs: &[u32], out: &mut W) -> Result<()> {
+        let n8 = P::NUM_LIMBS * 8;
+
+        let mut prime_bytes = self
+            .prime
+            .to_biguint()
+            .expect("the field prime is always positive")
+            .to_bytes_le();
+        prime_bytes.resize(n8, 0);
+
+        out.write_all(b"wtns")?;
+        out.write_u32::<LittleEndian>(2)?; // version
+        out.write_u32::<LittleEndian>(2)?; // number of sections
+
+        // Section 1: field size, in bytes (n8), the prime itself, and the witness count.
+        out.write_u32::<LittleEndian>(1)?;
+        out.write_u64::<LittleEndian>((4 + n8 + 4) as u64)?;
+        out.write_u32::<LittleEndian>(n8 as u32)?;
+        out.write_all(&prime_bytes)?;
+        out.write_u32::<LittleEndian>(witness_ptrs.len() as u32)?;
+
+        // Section 2: the witness values themselves, each `n8` little-endian bytes.
+        out.write_u32::<LittleEndian>(2)?;
+        out.write_u64::<LittleEndian>((n8 * witness_ptrs.len()) as u64)?;
+        for &ptr in witness_ptrs {
+            let fr = self.read_fr(ptr as usize)?;
+            let reduced = ((&fr % &self.prime) + &self.prime) % &self.prime;
+            let (_, reduced) = reduced.into_parts();
+            let reduced = P::BigInt::try_from(reduced).unwrap();
+            out.write_all(&reduced.to_bytes_le())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `fr` using a compact varint codec instead of `write_fr`'s fixed
+    /// `8 + NUM_LIMBS * 8` bytes: a one-byte tag (`0` = short positive, `1` = short
+    /// negative, `2` = long), followed by either an unsigned-LEB128 magnitude (short --
+    /// the common case for booleans and small indices) or `NUM_LIMBS * 8` little-endian
+    /// bytes (long, reduced to the canonical representative like `write_big`). Mirrors
+    /// the short/long split in `write_fr`, but sized for wire/storage rather than a fixed
+    /// WASM memory slot. Returns the number of bytes written.
+    pub fn write_fr_varint<W: Write>(&self, fr: &BigInt, out: &mut W) -> Result<usize> {
+        if fr < &self.short_max && fr > &self.short_min {
+            let (tag, magnitude) = if fr >= &BigInt::zero() {
+                (
+                    VARINT_TAG_SHORT_POSITIVE,
+                    fr.to_u64().expect("short value fits in a u64"),
+                )
+            } else {
+                (
+                    VARINT_TAG_SHORT_NEGATIVE,
+                    (-fr).to_u64().expect("short value fits in a u64"),
+                )
+            };
+            out.write_u8(tag)?;
+            let leb_len = write_uleb128(magnitude, out)?;
+            Ok(1 + leb_len)
+        } else {
+            let reduced = ((fr % &self.prime) + &self.prime) % &self.prime;
+            let (_, reduced) = reduced.into_parts();
+            let reduced = P::BigInt::try_from(reduced).unwrap();
+            let bytes = reduced.to_bytes_le();
+
+            out.write_u8(VARINT_TAG_LONG)?;
+            out.write_all(&bytes)?;
+            Ok(1 + bytes.len())
+        }
+    }
+
+    /// Reads a single value written by [`write_fr_varint`].
+    pub fn read_fr_varint<R: Read>(&self, input: &mut R) -> Result<BigInt> {
+        match input.read_u8()? {
+            VARINT_TAG_SHORT_POSITIVE => Ok(BigInt::from(read_uleb128(input)?)),
+            VARINT_TAG_SHORT_NEGATIVE => Ok(-BigInt::from(read_uleb128(input)?)),
+            VARINT_TAG_LONG => {
+                let mut bytes = vec![0u8; P::NUM_LIMBS * 8];
+                input.read_exact(&mut bytes)?;
+                let big = P::BigInt::read(&bytes[..]).unwrap();
+                // `BigInteger: Into<BigUint>` is infallible, unlike the `BigUint -> BigInteger`
+                // direction above.
+                let big: BigUint = big.into();
+                Ok(big.into())
+            }
+            tag => Err(color_eyre::eyre::eyre!("invalid varint witness tag: {}", tag)),
+        }
+    }
+
+    /// Serializes a full witness (or any sparse signal map) with [`write_fr_varint`],
+    /// prefixed with the element count so [`read_witness_varint`] knows how many values
+    /// to expect.
+    pub fn write_witness_varint<W: Write>(&self, witness: &[BigInt], out: &mut W) -> Result<()> {
+        out.write_u32::<LittleEndian>(witness.len() as u32)?;
+        for fr in witness {
+            self.write_fr_varint(fr, out)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a witness written by [`write_witness_varint`].
+    pub fn read_witness_varint<R: Read>(&self, input: &mut R) -> Result<Vec<BigInt>> {
+        let count = input.read_u32::<LittleEndian>()?;
+        (0..count).map(|_| self.read_fr_varint(input)).collect()
+    }
+
+    /// Reads `count` consecutive `T`s starting at `ptr`, each `T::MEM_SIZE` bytes apart.
+    /// The read-side counterpart to the `WriteMem` impl for `Vec<T>`, which doesn't
+    /// encode its own length in memory -- the caller has to supply it.
+    pub fn read_vec<T: ReadMem<P> + MemSize<P>>(&self, ptr: usize, count: usize) -> Result<Vec<T>> {
+        (0..count)
+            .map(|i| T::read_mem(self, ptr + i * T::MEM_SIZE))
+            .collect()
+    }
+}
+
+const VARINT_TAG_SHORT_POSITIVE: u8 = 0;
+const VARINT_TAG_SHORT_NEGATIVE: u8 = 1;
+const VARINT_TAG_LONG: u8 = 2;
+
+/// Encodes `value` as unsigned LEB128 (7 bits per byte, high bit set = more bytes
+/// follow), the same continuation-bit scheme rustc's `opaque.rs` and Kafka's varints use.
+fn write_uleb128<W: Write>(mut value: u64, out: &mut W) -> Result<usize> {
+    let mut len = 0;
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        out.write_u8(byte)?;
+        len += 1;
+        if value == 0 {
+            break;
+        }
+    }
+    Ok(len)
+}
+
+/// Decodes a value written by [`write_uleb128`]. Bails out once `shift` would run past
+/// the width of a `u64` instead of panicking (debug) or silently wrapping (release) on a
+/// corrupted/malicious stream of 10+ continuation bytes.
+fn read_uleb128<R: Read>(input: &mut R) -> Result<u64> {
+    let mut result: u64 = 0;
+    let mut shift = 0;
+    loop {
+        if shift >= 64 {
+            return Err(color_eyre::eyre::eyre!(
+                "uleb128 varint too long: continuation bytes exceed u64 width"
+            ));
+        }
+        let byte = input.read_u8()?;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Ok(result)
+}
+
+/// Writes `Self` into a [`SafeMem`] at `ptr`, so that callers don't have to reach for a
+/// type-specific `write_*` method on `SafeMem` itself.
+///
+/// Modeled on rust-lightning's `ser.rs`: every on-the-wire type gets a single trait impl
+/// instead of `SafeMem` growing a new ad-hoc method per type.
+pub trait WriteMem<P: FieldParams> {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()>;
+}
+
+/// Reads `Self` out of a [`SafeMem`] at `ptr`. The counterpart to [`WriteMem`].
+pub trait ReadMem<P: FieldParams>: Sized {
+    fn read_mem(mem: &SafeMem<P>, ptr: usize) -> Result<Self>;
+}
+
+/// The fixed number of bytes a [`WriteMem`]/[`ReadMem`] implementor occupies in WASM
+/// memory, so that a run of them (a witness vector, a signal bank) can be strided over
+/// without each caller re-deriving the element width by hand.
+pub trait MemSize<P: FieldParams> {
+    const MEM_SIZE: usize;
+}
+
+impl<P: FieldParams> MemSize<P> for u32 {
+    const MEM_SIZE: usize = 4;
+}
+
+impl<P: FieldParams> WriteMem<P> for u32 {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()> {
+        mem.write_u32(ptr, *self);
+        Ok(())
+    }
+}
+
+impl<P: FieldParams> ReadMem<P> for u32 {
+    fn read_mem(mem: &SafeMem<P>, ptr: usize) -> Result<Self> {
+        Ok(mem.read_u32(ptr))
+    }
+}
+
+impl<P: FieldParams> MemSize<P> for BigInt {
+    // Two header words plus `NUM_LIMBS` 64-bit limbs; see `SafeMem::alloc_fr`.
+    const MEM_SIZE: usize = 8 + P::NUM_LIMBS * 8;
+}
+
+impl<P: FieldParams> WriteMem<P> for BigInt {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()> {
+        mem.write_fr(ptr, self)
+    }
+}
+
+impl<P: FieldParams> ReadMem<P> for BigInt {
+    fn read_mem(mem: &SafeMem<P>, ptr: usize) -> Result<Self> {
+        mem.read_fr(ptr)
+    }
+}
+
+impl<P: FieldParams> MemSize<P> for Address {
+    const MEM_SIZE: usize = 20;
+}
+
+impl<P: FieldParams> WriteMem<P> for Address {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()> {
+        mem.write_bytes(ptr, self.as_bytes());
+        Ok(())
+    }
+}
+
+impl<P: FieldParams> ReadMem<P> for Address {
+    fn read_mem(mem: &SafeMem<P>, ptr: usize) -> Result<Self> {
+        Ok(Address::from_slice(&mem.read_bytes(ptr, 20)))
+    }
+}
+
+impl<P: FieldParams> MemSize<P> for U256 {
+    const MEM_SIZE: usize = 32;
+}
+
+impl<P: FieldParams> WriteMem<P> for U256 {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()> {
+        let mut bytes = [0u8; 32];
+        self.to_little_endian(&mut bytes);
+        mem.write_bytes(ptr, &bytes);
+        Ok(())
+    }
+}
+
+impl<P: FieldParams> ReadMem<P> for U256 {
+    fn read_mem(mem: &SafeMem<P>, ptr: usize) -> Result<Self> {
+        Ok(U256::from_little_endian(&mem.read_bytes(ptr, 32)))
+    }
+}
+
+/// Writes each element back-to-back, advancing by `T::MEM_SIZE` per item, starting at
+/// `ptr`. The element count lives in the `Vec` itself, so only the write direction is
+/// generic here -- reading a dynamically-sized run back out requires the caller to know
+/// how many elements to expect; see [`SafeMem::read_vec`] for that case, or use
+/// `[T; N]` for a count that's fixed at compile time.
+impl<P: FieldParams, T: WriteMem<P> + MemSize<P>> WriteMem<P> for Vec<T> {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()> {
+        for (i, item) in self.iter().enumerate() {
+            item.write_mem(mem, ptr + i * T::MEM_SIZE)?;
+        }
+        Ok(())
+    }
+}
+
+impl<P: FieldParams, T: WriteMem<P> + MemSize<P>, const N: usize> WriteMem<P> for [T; N] {
+    fn write_mem(&self, mem: &mut SafeMem<P>, ptr: usize) -> Result<()> {
+        for (i, item) in self.iter().enumerate() {
+            item.write_mem(mem, ptr + i * T::MEM_SIZE)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads `N` consecutive `T`s back out, the counterpart to the `[T; N]` `WriteMem` impl.
+/// Built on a `Vec` (rather than requiring `T: Copy` to pre-fill an array) so this works
+/// for `BigInt` -- the whole point of round-tripping witness vectors / signal banks.
+impl<P: FieldParams, T: ReadMem<P> + MemSize<P>, const N: usize> ReadMem<P> for [T; N] {
+    fn read_mem(mem: &SafeMem<P>, ptr: usize) -> Result<Self> {
+        let items = mem.read_vec::<T>(ptr, N)?;
+        items
+            .try_into()
+            .map_err(|_| color_eyre::eyre::eyre!("expected {} elements, got a different count", N))
+    }
 }
 
-// TODO: Figure out how to read / write numbers > u32
 // circom-witness-calculator: Wasm + Memory -> expose BigInts so that they can be consumed by any proof system
 // ark-circom:
 // 1. can read zkey
@@ -196,7 +597,6 @@ mod tests {
     fn new() -> SafeMem {
         SafeMem::new(
             Memory::new(&Store::default(), MemoryType::new(1, None, false)).unwrap(),
-            2,
             BigInt::from_str(
                 "21888242871839275222246405745257275088548364400416034343698204186575808495617",
             )
@@ -208,6 +608,8 @@ mod tests {
     fn i32_bounds() {
         let mem = new();
         let i32_max = i32::MAX as i64 + 1;
+        // Curve-independent: the short/long threshold is `±2^31` for every field, not
+        // something derived from the modulus (see the comment in `SafeMem::with_endianness`).
         assert_eq!(mem.short_min.to_i64().unwrap(), -i32_max);
         assert_eq!(mem.short_max.to_i64().unwrap(), i32_max);
     }
@@ -243,15 +645,14 @@ mod tests {
         read_write_fr(BigInt::from(500000000000i64), BigInt::from(500000000000i64));
     }
 
-    // TODO: How should this be handled?
     #[test]
     fn read_write_fr_big_negative() {
         read_write_fr(
             BigInt::from_str("-500000000000").unwrap(),
-            BigInt::from_str("-500000000000").unwrap(),
-            // "21888242871839275222246405745257275088548364400416034343698204186574024701953"
-            //     .parse()
-            //     .unwrap(),
+            // canonical representative `p - 500000000000`
+            "21888242871839275222246405745257275088548364400416034343698204186075808495617"
+                .parse()
+                .unwrap(),
         )
     }
 
@@ -261,4 +662,154 @@ mod tests {
         let res = mem.read_fr(0).unwrap();
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn varint_zero() {
+        read_write_fr_varint(BigInt::zero());
+    }
+
+    #[test]
+    fn varint_short_positive() {
+        read_write_fr_varint(BigInt::from(1_000_000));
+    }
+
+    #[test]
+    fn varint_short_negative() {
+        read_write_fr_varint(BigInt::from(-1_000_000));
+    }
+
+    #[test]
+    fn varint_full_width() {
+        read_write_fr_varint(
+            BigInt::from_str(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495616",
+            )
+            .unwrap(),
+        );
+    }
+
+    fn read_write_fr_varint(fr: BigInt) {
+        let mem = new();
+        let mut buf = Vec::new();
+        mem.write_fr_varint(&fr, &mut buf).unwrap();
+        let res = mem.read_fr_varint(&mut &buf[..]).unwrap();
+        assert_eq!(res, fr);
+    }
+
+    #[test]
+    fn witness_varint_roundtrip() {
+        let mem = new();
+        let witness = vec![
+            BigInt::zero(),
+            BigInt::from(1_000_000),
+            BigInt::from(-1_000_000),
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+                .parse()
+                .unwrap(),
+        ];
+
+        let mut buf = Vec::new();
+        mem.write_witness_varint(&witness, &mut buf).unwrap();
+        let res = mem.read_witness_varint(&mut &buf[..]).unwrap();
+        assert_eq!(res, witness);
+    }
+
+    #[test]
+    fn varint_corrupted_continuation_bytes_errors() {
+        let mem = new();
+        // A short-positive tag followed by 10 bytes that all set the continuation bit --
+        // a well-formed u64 varint is at most 10 bytes, but none of these ever clear the
+        // high bit, so a corrupted/malicious stream can't drive `shift` past 64.
+        let buf = [&[VARINT_TAG_SHORT_POSITIVE][..], &[0x80; 10][..]].concat();
+        assert!(mem.read_fr_varint(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn varint_invalid_tag_errors() {
+        let mem = new();
+        let buf = [3u8];
+        assert!(mem.read_fr_varint(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn read_write_fr_array() {
+        let mut mem = new();
+        let signals: [BigInt; 3] = [
+            BigInt::from(0),
+            BigInt::from(-1_000_000),
+            BigInt::from(500000000000i64),
+        ];
+
+        signals.write_mem(&mut mem, 0).unwrap();
+        let res = <[BigInt; 3]>::read_mem(&mem, 0).unwrap();
+        assert_eq!(res, signals);
+    }
+
+    #[test]
+    fn read_vec_fr() {
+        let mut mem = new();
+        let signals = vec![BigInt::from(0), BigInt::from(-1_000_000)];
+
+        signals.write_mem(&mut mem, 0).unwrap();
+        let res: Vec<BigInt> = mem.read_vec(0, signals.len()).unwrap();
+        assert_eq!(res, signals);
+    }
+
+    #[test]
+    fn dump_witness_byte_layout() {
+        let mut mem = new();
+        let witness = vec![BigInt::from(1_000_000), BigInt::from(-1)];
+
+        // Address 0 holds the free pointer itself, so start allocating past it.
+        mem.set_free_pos(8);
+        let ptrs: Vec<u32> = witness
+            .iter()
+            .map(|fr| {
+                let ptr = mem.alloc_fr();
+                mem.write_fr(ptr as usize, fr).unwrap();
+                ptr
+            })
+            .collect();
+
+        let mut buf = Vec::new();
+        mem.dump_witness(&ptrs, &mut buf).unwrap();
+
+        let n8 = 32; // Bn254Params::NUM_LIMBS * 8
+        let mut prime_bytes = mem.prime.to_biguint().unwrap().to_bytes_le();
+        prime_bytes.resize(n8, 0);
+
+        assert_eq!(&buf[0..4], b"wtns");
+        assert_eq!(&buf[4..8], &2u32.to_le_bytes()); // version
+        assert_eq!(&buf[8..12], &2u32.to_le_bytes()); // number of sections
+
+        assert_eq!(&buf[12..16], &1u32.to_le_bytes()); // section 1 id
+        let section1_size = (4 + n8 + 4) as u64;
+        assert_eq!(&buf[16..24], &section1_size.to_le_bytes());
+        assert_eq!(&buf[24..28], &(n8 as u32).to_le_bytes());
+        assert_eq!(&buf[28..28 + n8], &prime_bytes[..]);
+        let witness_count_offset = 28 + n8;
+        assert_eq!(
+            &buf[witness_count_offset..witness_count_offset + 4],
+            &(witness.len() as u32).to_le_bytes()
+        );
+
+        let section2_offset = witness_count_offset + 4;
+        assert_eq!(&buf[section2_offset..section2_offset + 4], &2u32.to_le_bytes()); // section 2 id
+        let section2_size = (n8 * witness.len()) as u64;
+        assert_eq!(
+            &buf[section2_offset + 4..section2_offset + 12],
+            &section2_size.to_le_bytes()
+        );
+
+        let values_offset = section2_offset + 12;
+        for (i, fr) in witness.iter().enumerate() {
+            let reduced = ((fr % &mem.prime) + &mem.prime) % &mem.prime;
+            let mut expected = reduced.to_biguint().unwrap().to_bytes_le();
+            expected.resize(n8, 0);
+            let start = values_offset + i * n8;
+            assert_eq!(&buf[start..start + n8], &expected[..]);
+        }
+
+        assert_eq!(buf.len(), values_offset + n8 * witness.len());
+    }
 }